@@ -0,0 +1,238 @@
+// ASCII-armors the output of `cipher::encode` into a
+// self-describing text block, the way OpenPGP wraps a
+// message in `-----BEGIN PGP MESSAGE-----` markers plus
+// a trailing checksum line. This lets ciphertext be
+// pasted around as plain text while still catching
+// truncation or tampering on the way back in.
+
+const BEGIN_MARKER: &str = "-----BEGIN VIGENERE MESSAGE-----";
+const END_MARKER: &str = "-----END VIGENERE MESSAGE-----";
+
+#[derive(Debug)]
+pub(crate) enum ArmorError {
+    MissingBeginMarker,
+    MissingEndMarker,
+    MissingChecksum,
+    MalformedChecksum,
+    MalformedPayload,
+    ChecksumMismatch,
+}
+
+// Wraps `ciphertext` (as produced by `cipher::encode`)
+// in a header, the base64 of the ciphertext itself (like
+// real PGP armor, so no line of the payload can collide
+// with a marker or contain a literal newline), a CRC-24
+// checksum line, and a footer.
+pub(crate) fn armor(ciphertext: &str) -> String {
+    let checksum = crc24(ciphertext.as_bytes());
+    let mut block = String::new();
+    block.push_str(BEGIN_MARKER);
+    block.push('\n');
+    block.push_str(&base64_encode(ciphertext.as_bytes()));
+    block.push('\n');
+    block.push('=');
+    block.push_str(&base64_encode(&checksum.to_be_bytes()[1..4]));
+    block.push('\n');
+    block.push_str(END_MARKER);
+    block
+}
+
+// Validates and strips the armor from `block`, returning
+// the ciphertext payload ready to be handed to
+// `cipher::decode`. Fails if either marker is missing, the
+// checksum line is malformed, or the checksum doesn't
+// match the enclosed ciphertext (a sign of truncation or
+// tampering).
+//
+// The payload line is base64, not the raw ciphertext, so
+// there's no ambiguity to resolve here between a literal
+// newline inside the ciphertext and the line break the
+// armor format itself inserts - see `armor`.
+pub(crate) fn dearmor(block: &str) -> Result<String, ArmorError> {
+    let mut lines = block.lines();
+
+    match lines.next() {
+        Some(line) if line.trim() == BEGIN_MARKER => {}
+        _ => return Err(ArmorError::MissingBeginMarker),
+    }
+
+    let remaining: Vec<&str> = lines.collect();
+    let end_pos = remaining
+        .iter()
+        .rposition(|line| line.trim() == END_MARKER)
+        .ok_or(ArmorError::MissingEndMarker)?;
+    let checksum_pos = end_pos
+        .checked_sub(1)
+        .ok_or(ArmorError::MissingChecksum)?;
+
+    let checksum_line = remaining[checksum_pos];
+    let encoded_checksum = checksum_line
+        .strip_prefix('=')
+        .ok_or(ArmorError::MalformedChecksum)?;
+    let checksum_bytes =
+        base64_decode(encoded_checksum).map_err(|_| ArmorError::MalformedChecksum)?;
+    if checksum_bytes.len() != 3 {
+        return Err(ArmorError::MalformedChecksum);
+    }
+
+    if checksum_pos != 1 {
+        return Err(ArmorError::MalformedPayload);
+    }
+    let encoded_payload = remaining[0];
+    let payload_bytes =
+        base64_decode(encoded_payload).map_err(|_| ArmorError::MalformedPayload)?;
+    let ciphertext =
+        String::from_utf8(payload_bytes).map_err(|_| ArmorError::MalformedPayload)?;
+
+    let expected = crc24(ciphertext.as_bytes()).to_be_bytes();
+    if checksum_bytes[..] != expected[1..4] {
+        return Err(ArmorError::ChecksumMismatch);
+    }
+
+    Ok(ciphertext)
+}
+
+// CRC-24 as used by OpenPGP's ASCII armor: init register
+// to 0x00B704CE, XOR each byte into bits 16-23, then run
+// 8 rounds of left-shift-and-conditionally-XOR with the
+// polynomial 0x01864CFB, masking the final register to
+// 24 bits.
+fn crc24(data: &[u8]) -> u32 {
+    const INIT: u32 = 0x00B7_04CE;
+    const POLY: u32 = 0x0186_4CFB;
+
+    let mut crc = INIT;
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= POLY;
+            }
+        }
+    }
+    crc & 0x00FF_FFFF
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let triple = (b0 << 16) | (b1 << 8) | b2;
+
+        out.push(BASE64_ALPHABET[((triple >> 18) & 0x3F) as usize] as char);
+        out.push(BASE64_ALPHABET[((triple >> 12) & 0x3F) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            BASE64_ALPHABET[((triple >> 6) & 0x3F) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            BASE64_ALPHABET[(triple & 0x3F) as usize] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+fn base64_decode(encoded: &str) -> Result<Vec<u8>, ()> {
+    fn value_of(ch: u8) -> Result<u32, ()> {
+        BASE64_ALPHABET
+            .iter()
+            .position(|&c| c == ch)
+            .map(|idx| idx as u32)
+            .ok_or(())
+    }
+
+    let trimmed = encoded.trim_end_matches('=');
+    let chars: Vec<u8> = trimmed.bytes().collect();
+    let mut out = Vec::new();
+
+    for chunk in chars.chunks(4) {
+        if chunk.len() < 2 {
+            return Err(());
+        }
+        let v0 = value_of(chunk[0])?;
+        let v1 = value_of(chunk[1])?;
+        let triple = (v0 << 18) | (v1 << 12);
+        out.push((triple >> 16) as u8);
+
+        if chunk.len() > 2 {
+            let v2 = value_of(chunk[2])?;
+            let triple = triple | (v2 << 6);
+            out.push((triple >> 8) as u8);
+
+            if chunk.len() > 3 {
+                let v3 = value_of(chunk[3])?;
+                let triple = triple | v3;
+                out.push(triple as u8);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    #[test]
+    fn test_armor_roundtrip() {
+        let ciphertext = "Hello, World!";
+        let armored = armor(ciphertext);
+        assert!(armored.starts_with(BEGIN_MARKER));
+        assert!(armored.ends_with(END_MARKER));
+        assert_eq!(ciphertext, dearmor(&armored).unwrap());
+    }
+
+    #[test]
+    fn test_armor_detects_tampering() {
+        let ciphertext = "Hello, World!";
+        let armored = armor(ciphertext);
+        let mut lines: Vec<&str> = armored.lines().collect();
+        let mut payload_chars: Vec<char> = lines[1].chars().collect();
+        payload_chars[0] = if payload_chars[0] == 'A' { 'B' } else { 'A' };
+        let tampered_payload: String = payload_chars.into_iter().collect();
+        lines[1] = &tampered_payload;
+        let tampered = lines.join("\n");
+        assert!(matches!(
+            dearmor(&tampered),
+            Err(ArmorError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_armor_roundtrip_preserves_embedded_crlf() {
+        // the payload is base64, not raw text, so a
+        // literal "\r\n" inside the ciphertext can't be
+        // confused with the armor format's own line
+        // breaks.
+        let ciphertext = "AB\r\nCD";
+        let armored = armor(ciphertext);
+        assert_eq!(ciphertext, dearmor(&armored).unwrap());
+    }
+
+    #[test]
+    fn test_armor_detects_truncation() {
+        let ciphertext = "Hello, World!";
+        let armored = armor(ciphertext);
+        let truncated = &armored[..armored.len() - 40];
+        assert!(dearmor(truncated).is_err());
+    }
+
+    #[test]
+    fn test_base64_roundtrip() {
+        let bytes = [0x01u8, 0x86, 0x4C];
+        let encoded = base64_encode(&bytes);
+        assert_eq!(bytes.to_vec(), base64_decode(&encoded).unwrap());
+        assert_ne!(encoded, base64_encode(&[0x00, 0x00, 0x00]));
+    }
+}
@@ -1,88 +1,227 @@
-// Size of the 'dictionary'
-// (all non-control ASCII characters plus '\n' and '\r')
-pub(crate) const SIZE: usize = 192;
+use std::collections::{HashMap, HashSet};
 
-// Tuple struct wrapper around an array of
-// characters of size 192
-#[derive(Clone, Copy)]
-pub(crate) struct DictWrap(pub(crate) [char; SIZE]);
+use crate::secret_key::{SecretKey, Zeroizing};
+
+// Tuple struct wrapper around the ordered list of
+// characters that make up the Vigenère tableau's
+// alphabet. Unlike a fixed-size array, this can hold
+// any alphabet the caller provides (Cyrillic, Greek,
+// a narrow ASCII set, ...), not just the built-in
+// Latin-1 dictionary.
+#[derive(Clone)]
+pub(crate) struct DictWrap(pub(crate) Vec<char>);
 
 #[derive(Debug)]
 pub(crate) enum ErrorCode {
     InvalidChar(char),
     InvalidIndex(usize),
+    EmptyAlphabet,
+    DuplicateChar(char),
+    EmptyKey,
 }
 
-// Creates and returns a new dictionary
-// for the Vigenère matrix
 impl DictWrap {
-    pub(crate) fn new() -> DictWrap {
-        // Every ASCII character that !is_control().
-        let mut dict = r##"!"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\]^_`abcdefghijklmnopqrstuvwxyz{|}~ ¡¢£¤¥¦§¨©ª«¬­®¯°±²³´µ¶·¸¹º»¼½¾¿ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚÛÜÝÞßàáâãäåæçèéêëìíîïðñòóôõö÷øùúûüýþÿ"##.to_string();
-        // Add carriage return to support in web textarea
-        dict.push('\n');
-        dict.push('\r');
-        let mut dict_char_arr = [' '; SIZE];
-        for (idx, ch) in dict.chars().enumerate() {
-            dict_char_arr[idx] = ch;
-        }
-        DictWrap(dict_char_arr)
+    // Builds a dictionary from an already-validated,
+    // ordered, de-duplicated slice of characters. Use
+    // `Cipher::new` if the alphabet hasn't been
+    // validated yet.
+    pub(crate) fn new(alphabet: &[char]) -> DictWrap {
+        DictWrap(alphabet.to_vec())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
     pub(crate) fn get_string(&self) -> String {
-        let mut s = String::new();
-        for ch in self.0 {
-            s.push(ch);
-        }
-        s
+        self.0.iter().collect()
     }
 }
 
-// Again using the `Newtype Pattern`, create
-// a tuple struct wrapper around the 2D array
-#[derive(Clone, Copy)]
-pub(crate) struct VigMatrixWrap(pub(crate) [[char; SIZE]; SIZE]);
+// Every non-control Latin-1 character except the
+// no-break space (U+00A0), plus '\n' and '\r' to support
+// multi-line text in a web textarea: 192 characters.
+// This is the alphabet the cipher used before it became
+// configurable, kept around as the default.
+fn default_alphabet() -> Vec<char> {
+    let mut dict = r##"!"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\]^_`abcdefghijklmnopqrstuvwxyz{|}~ ¡¢£¤¥¦§¨©ª«¬­®¯°±²³´µ¶·¸¹º»¼½¾¿ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚÛÜÝÞßàáâãäåæçèéêëìíîïðñòóôõö÷øùúûüýþÿ"##.to_string();
+    dict.push('\n');
+    dict.push('\r');
+    dict.chars().collect()
+}
+
+// The Vigenère tableau. Cell `[r][c]` is, by
+// construction, the dictionary character at index
+// `(r + c) mod dict.len()` — so rather than materialize
+// the full `size * size` tableau, this stores just the
+// dictionary order plus a reverse lookup from character
+// to column, and computes any cell on demand via that
+// algebraic identity. `col_index` is precomputed once
+// here so `col_of` is a hash lookup instead of a linear
+// scan over the dictionary.
+#[derive(Clone)]
+pub(crate) struct VigMatrixWrap {
+    dict: Vec<char>,
+    col_index: HashMap<char, usize>,
+}
 
-// Creates and returns a new Vigenère Matrix
 impl VigMatrixWrap {
-    pub(crate) fn new() -> VigMatrixWrap {
-        let mut matrix: VigMatrixWrap = VigMatrixWrap([[' '; SIZE]; SIZE]);
-        // Get the array of dictionary characters
-        let binding = DictWrap::new().0;
-        // Create a cyclical (i.e. never ending) iterator
-        // cycle() repeats an interator endlessly
-        let mut acc = binding.iter().cycle();
-
-        for r in 0..matrix.0.len() {
-            for c in 0..matrix.0.len() {
-                matrix.0[r][c] = *acc.next().unwrap();
+    pub(crate) fn new(dict: &DictWrap) -> VigMatrixWrap {
+        let col_index = dict.0.iter().enumerate().map(|(idx, &ch)| (ch, idx)).collect();
+        VigMatrixWrap {
+            dict: dict.0.clone(),
+            col_index,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.dict.len()
+    }
+
+    // Constant-time lookup of a dictionary character's
+    // column index, backed by the precomputed map
+    // instead of a linear scan.
+    fn col_of(&self, ch: char) -> Result<usize, ErrorCode> {
+        self.col_index
+            .get(&ch)
+            .copied()
+            .ok_or(ErrorCode::InvalidChar(ch))
+    }
+
+    // Constant-time reverse lookup: the dictionary
+    // character at column `idx`.
+    fn char_at(&self, idx: usize) -> Result<char, ErrorCode> {
+        self.dict.get(idx).copied().ok_or(ErrorCode::InvalidIndex(idx))
+    }
+}
+
+// A Vigenère tableau bound to a specific alphabet.
+// Owns the dictionary and matrix it was built from and
+// exposes `encode`/`decode`/`decode_web` as methods, so
+// callers no longer have to carry a matching `DictWrap`
+// and `VigMatrixWrap` around by hand.
+pub(crate) struct Cipher {
+    dict: DictWrap,
+    matrix: VigMatrixWrap,
+}
+
+impl Cipher {
+    // Validates `alphabet` (non-empty, no duplicates)
+    // and builds the matching tableau.
+    pub(crate) fn new(alphabet: &[char]) -> Result<Cipher, ErrorCode> {
+        if alphabet.is_empty() {
+            return Err(ErrorCode::EmptyAlphabet);
+        }
+
+        let mut seen = HashSet::with_capacity(alphabet.len());
+        for &ch in alphabet {
+            if !seen.insert(ch) {
+                return Err(ErrorCode::DuplicateChar(ch));
             }
-            // this will start the next
-            // loop at the next character
-            // as the first item
-            acc.next();
         }
-        matrix
+
+        let dict = DictWrap::new(alphabet);
+        let matrix = VigMatrixWrap::new(&dict);
+        Ok(Cipher { dict, matrix })
+    }
+
+    pub(crate) fn dict(&self) -> &DictWrap {
+        &self.dict
+    }
+
+    pub(crate) fn encode(
+        &self,
+        msg: &str,
+        key: &SecretKey,
+        mode: KeyMode,
+    ) -> Result<String, ErrorCode> {
+        encode(msg, key, &self.matrix, mode)
+    }
+
+    pub(crate) fn decode(
+        &self,
+        enc_msg: &str,
+        key: &SecretKey,
+        mode: KeyMode,
+    ) -> Result<String, ErrorCode> {
+        decode(enc_msg, key, &self.matrix, mode)
+    }
+
+    pub(crate) fn decode_web(
+        &self,
+        enc_msg: &str,
+        key: &SecretKey,
+        mode: KeyMode,
+    ) -> Result<String, ErrorCode> {
+        decode_web(enc_msg, key, &self.matrix, mode)
+    }
+}
+
+// Builds a cipher over the built-in Latin-1 alphabet
+// that this crate shipped with before the alphabet
+// became configurable.
+impl Default for Cipher {
+    fn default() -> Cipher {
+        Cipher::new(&default_alphabet()).expect("default alphabet has no duplicates")
     }
 }
 
+// Selects how the key is stretched to cover
+// a message longer than the key itself.
+//
+// `Repeating` is the classic Vigenère behavior
+// (the key just cycles), which leaks its period
+// to frequency analysis (Kasiski examination).
+// `Autokey` instead appends the plaintext itself
+// to the key, so the running key never repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyMode {
+    Repeating,
+    Autokey,
+}
+
 // Completes the key if the key size is not
 // the same as the message.
-// In other words, extends the key String to
-// be the same size as the message String.
-fn complete_key(key: &str, msg_size: usize) -> String {
+// In other words, extends the key to be the
+// same size as the message, holding the
+// expansion in a zeroizing buffer rather than
+// a bare `String` since it's still key material.
+fn complete_key(key: &str, msg_size: usize) -> Zeroizing {
     // cycle() repeats an interator endlessly
     let mut key_chars = key.chars().cycle();
-    let mut new_key = "".to_string();
+    let mut new_key = String::with_capacity(msg_size);
     for _ in 0..msg_size {
         new_key.push(key_chars.next().unwrap());
     }
-    new_key
+    Zeroizing::new(new_key.into_bytes())
+}
+
+// Builds the autokey running key: the supplied
+// key followed by the plaintext itself, truncated
+// to the message length. Also held in a zeroizing
+// buffer, since the prefix is still key material.
+fn complete_key_autokey(key: &str, msg: &str, msg_size: usize) -> Zeroizing {
+    let running_key: String = key.chars().chain(msg.chars()).take(msg_size).collect();
+    Zeroizing::new(running_key.into_bytes())
 }
 
 // Encodes a message (msg) with a key(key)
 // using a Vigenère Matrix (vig_mat)
-pub(crate) fn encode(msg: &str, key: &str, vig_mat: VigMatrixWrap) -> Result<String, ErrorCode> {
+pub(crate) fn encode(
+    msg: &str,
+    key: &SecretKey,
+    vig_mat: &VigMatrixWrap,
+    mode: KeyMode,
+) -> Result<String, ErrorCode> {
+    let key = key.as_str();
+    if key.is_empty() {
+        return Err(ErrorCode::EmptyKey);
+    }
+
     // get size of message and key
     let msg_size = msg.chars().count();
     let key_size = key.chars().count();
@@ -90,19 +229,22 @@ pub(crate) fn encode(msg: &str, key: &str, vig_mat: VigMatrixWrap) -> Result<Str
     // initialisations
     let mut encrypted_msg = "".to_string();
 
-    // if key has a different size, then complete it
-    let mut key_e = key.to_string();
-    if msg_size != key_size {
-        key_e = complete_key(key, msg_size);
-    }
+    // stretch the key to the message length,
+    // either by repeating it or by chaining
+    // on the plaintext (autokey)
+    let key_e = match mode {
+        KeyMode::Repeating if msg_size != key_size => complete_key(key, msg_size),
+        KeyMode::Repeating => Zeroizing::new(key.as_bytes().to_vec()),
+        KeyMode::Autokey => complete_key_autokey(key, msg, msg_size),
+    };
 
     // convert to char vectors
-    let key_chars: Vec<_> = key_e.to_string().chars().collect();
+    let key_chars: Vec<_> = key_e.as_str().chars().collect();
     let msg_chars: Vec<_> = msg.to_string().chars().collect();
 
     // encrypt message
     for i in 0..msg_size {
-        encrypted_msg.push(vig_matcher(&vig_mat, msg_chars[i], key_chars[i])?);
+        encrypted_msg.push(vig_matcher(vig_mat, msg_chars[i], key_chars[i])?);
     }
 
     Ok(encrypted_msg)
@@ -112,62 +254,103 @@ pub(crate) fn encode(msg: &str, key: &str, vig_mat: VigMatrixWrap) -> Result<Str
 // depending on the header (msg_char) and column (key_char)
 // characters provided
 fn vig_matcher(matrix: &VigMatrixWrap, msg_char: char, key_char: char) -> Result<char, ErrorCode> {
-    let index_col = index_finder(msg_char, &matrix)?;
-    let index_row = index_finder(key_char, &matrix)?;
+    let index_col = index_finder(msg_char, matrix)?;
+    let index_row = index_finder(key_char, matrix)?;
 
-    Ok(matrix.0[index_row][index_col])
+    char_finder((index_row + index_col) % matrix.size(), matrix)
 }
 
-// Returns the index value of a char
-// in the Vigenère Matrix
+// Returns the index value of a char in the Vigenère
+// Matrix. Thin compatibility shim over `VigMatrixWrap`'s
+// precomputed, constant-time column lookup.
 fn index_finder(ch: char, matrix: &VigMatrixWrap) -> Result<usize, ErrorCode> {
-    for (index, val) in matrix.0[0].iter().enumerate() {
-        if ch == *val {
-            return Ok(index);
-        }
-    }
-    Err(ErrorCode::InvalidChar(ch))
+    matrix.col_of(ch)
 }
 
 // Decodes an encoded message (enc_msg) with
-// a key (key) and a Vigenère Matrix (vig_mat)
+// a key (key) and a Vigenère Matrix (vig_mat).
+//
+// For `KeyMode::Autokey` the running key can't be
+// built up front like in `encode`, since positions
+// beyond the supplied key depend on plaintext that
+// is only known once it has itself been decoded. So
+// the running key is grown one character at a time
+// as decoding proceeds: with `k` the key's length
+// in characters, position `i >= k` is decrypted
+// against the already-recovered plaintext
+// character at `i - k`.
 pub(crate) fn decode(
     enc_msg: &str,
-    key: &str,
-    vig_mat: VigMatrixWrap,
+    key: &SecretKey,
+    vig_mat: &VigMatrixWrap,
+    mode: KeyMode,
 ) -> Result<String, ErrorCode> {
+    let key = key.as_str();
+    if key.is_empty() {
+        return Err(ErrorCode::EmptyKey);
+    }
+
     // get size of message and key
     let msg_size = enc_msg.chars().count();
     let key_size = key.chars().count();
 
     // initialisations
     let mut decrypted_msg = "".to_string();
+    let msg_chars: Vec<_> = enc_msg.to_string().chars().collect();
 
-    // if key has a different size, then complete it
-    let mut key_e = key.to_string();
-    if msg_size != key_size {
-        key_e = complete_key(key, msg_size);
-    }
+    match mode {
+        KeyMode::Repeating => {
+            // if key has a different size, then complete it
+            let key_e = if msg_size != key_size {
+                complete_key(key, msg_size)
+            } else {
+                Zeroizing::new(key.as_bytes().to_vec())
+            };
+            let key_chars: Vec<_> = key_e.as_str().chars().collect();
 
-    // convert to char vectors
-    let key_chars: Vec<_> = key_e.to_string().chars().collect();
-    let msg_chars: Vec<_> = enc_msg.to_string().chars().collect();
+            for letter in 0..msg_size {
+                let plain_char = vig_unmatcher(vig_mat, key_chars[letter], msg_chars[letter])?;
+                decrypted_msg.push(plain_char);
+            }
+        }
+        KeyMode::Autokey => {
+            let key_chars: Vec<_> = key.chars().collect();
+            let mut plain_chars: Vec<char> = Vec::with_capacity(msg_size);
 
-    // decrypt message
-    for letter in 0..msg_size {
-        let mut msg_index = 0;
-        let key_index = index_finder(key_chars[letter], &vig_mat)?;
-        for c in 0..vig_mat.0.len() {
-            if vig_mat.0[key_index][c] == msg_chars[letter] {
-                msg_index = c;
+            for letter in 0..msg_size {
+                let running_key_char = if letter < key_chars.len() {
+                    key_chars[letter]
+                } else {
+                    plain_chars[letter - key_chars.len()]
+                };
+                let plain_char = vig_unmatcher(vig_mat, running_key_char, msg_chars[letter])?;
+                plain_chars.push(plain_char);
+                decrypted_msg.push(plain_char);
             }
         }
-        decrypted_msg.push(char_finder(msg_index, &vig_mat)?);
     }
 
     Ok(decrypted_msg)
 }
 
+// Returns the plaintext character that, under
+// `key_char`, encodes to `enc_char` in the given
+// Vigenère Matrix. This is the inverse of
+// `vig_matcher`: `(r + c) mod size == col(enc_char)`
+// and `r == col(key_char)`, so `c` (the plaintext's
+// column) is `(col(enc_char) - col(key_char)) mod size`.
+fn vig_unmatcher(
+    matrix: &VigMatrixWrap,
+    key_char: char,
+    enc_char: char,
+) -> Result<char, ErrorCode> {
+    let col_key = index_finder(key_char, matrix)? as isize;
+    let col_enc = index_finder(enc_char, matrix)? as isize;
+    let size = matrix.size() as isize;
+    let msg_index = (col_enc - col_key).rem_euclid(size) as usize;
+    char_finder(msg_index, matrix)
+}
+
 // Decodes a message (msg) with a key (key)
 // using a Vigenère Matrix (vig_mat).
 // Returns the blank space char ' ' as '&nbsp;'
@@ -175,10 +358,11 @@ pub(crate) fn decode(
 // rendered properly on the browser
 pub(crate) fn decode_web(
     enc_msg: &str,
-    key: &str,
-    vig_mat: VigMatrixWrap,
+    key: &SecretKey,
+    vig_mat: &VigMatrixWrap,
+    mode: KeyMode,
 ) -> Result<String, ErrorCode> {
-    let decoded = decode(enc_msg, key, vig_mat)?;
+    let decoded = decode(enc_msg, key, vig_mat, mode)?;
     let mut decoded_web = "".to_string();
     for ch in decoded.chars() {
         match ch {
@@ -190,15 +374,11 @@ pub(crate) fn decode_web(
     Ok(decoded_web)
 }
 
-// Returns the char value of
-// an index in the Vigenère Matrix
+// Returns the char value of an index in the Vigenère
+// Matrix. Thin compatibility shim over `VigMatrixWrap`'s
+// constant-time reverse lookup.
 fn char_finder(index: usize, mat: &VigMatrixWrap) -> Result<char, ErrorCode> {
-    for (idx, val) in mat.0[0].iter().enumerate() {
-        if index == idx {
-            return Ok(*val);
-        }
-    }
-    Err(ErrorCode::InvalidIndex(index))
+    mat.char_at(index)
 }
 
 #[cfg(test)]
@@ -208,44 +388,124 @@ mod tests {
 
     #[test]
     fn test_encode() {
-        let vig_mat = VigMatrixWrap::new();
-        let key = "°¡! RüST íS CóÓL ¡!°";
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡! RüST íS CóÓL ¡!°");
         let message = "Hello, World!";
-        let encoded = encode(message, key, vig_mat).unwrap();
-        assert_eq!(message, decode(&encoded, key, vig_mat).unwrap());
+        let encoded = encode(message, &key, &vig_mat, KeyMode::Repeating).unwrap();
+        assert_eq!(message, decode(&encoded, &key, &vig_mat, KeyMode::Repeating).unwrap());
 
-        let vig_mat = VigMatrixWrap::new();
-        let key = "°¡! RüST íS CóÓL ¡!°";
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡! RüST íS CóÓL ¡!°");
         let message = "Anup Jadhav";
-        let encoded = encode(message, key, vig_mat).unwrap();
-        let decoded = decode(&encoded, key, vig_mat).unwrap();
+        let encoded = encode(message, &key, &vig_mat, KeyMode::Repeating).unwrap();
+        let decoded = decode(&encoded, &key, &vig_mat, KeyMode::Repeating).unwrap();
         // println!("key      :##{}##:", key);
         // println!("message  :##{}##:", message);
         // println!("encoded  :##{}##:", encoded);
         // println!("decoded  :##{}##:", decoded);
         assert_eq!(message, decoded);
 
-        let vig_mat = VigMatrixWrap::new();
-        let key = "°¡! RüST íS CóÓL ¡!°";
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡! RüST íS CóÓL ¡!°");
         let message = "!!!!";
-        let encoded = encode(message, key, vig_mat).unwrap();
-        assert_eq!(message, decode(&encoded, key, vig_mat).unwrap());
+        let encoded = encode(message, &key, &vig_mat, KeyMode::Repeating).unwrap();
+        assert_eq!(message, decode(&encoded, &key, &vig_mat, KeyMode::Repeating).unwrap());
 
-        let vig_mat = VigMatrixWrap::new();
-        let key = "°¡! RüST íS CóÓL ¡!°";
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡! RüST íS CóÓL ¡!°");
         let message = "WhátisApp+éars to   be the   problem here__°¿¿¿¿¿!!!!++++{{{{{{{}}}}}}}";
-        let encoded = encode(message, key, vig_mat).unwrap();
-        assert_eq!(message, decode(&encoded, key, vig_mat).unwrap());
+        let encoded = encode(message, &key, &vig_mat, KeyMode::Repeating).unwrap();
+        assert_eq!(message, decode(&encoded, &key, &vig_mat, KeyMode::Repeating).unwrap());
     }
 
     #[test]
     fn test_complex() {
-        let vig_mat = VigMatrixWrap::new();
-        let key = "°¡! RüST íS CóÓL ¡!°";
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡! RüST íS CóÓL ¡!°");
         let message = r##"´+++´[[[    {{{'''''""""()*&^   
             $2374954904890~~~11939455    
             7+a+e{eíóúúááÉú}"}}}]]]"##;
-        let encoded = encode(message, key, vig_mat).unwrap();
-        assert_eq!(message, decode(&encoded, key, vig_mat).unwrap());
+        let encoded = encode(message, &key, &vig_mat, KeyMode::Repeating).unwrap();
+        assert_eq!(message, decode(&encoded, &key, &vig_mat, KeyMode::Repeating).unwrap());
+    }
+
+    #[test]
+    fn test_autokey() {
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡! RüST íS CóÓL ¡!°");
+        let message = "Hello, World!";
+        let encoded = encode(message, &key, &vig_mat, KeyMode::Autokey).unwrap();
+        assert_eq!(message, decode(&encoded, &key, &vig_mat, KeyMode::Autokey).unwrap());
+
+        // a short key still works: positions beyond the
+        // key length run against the recovered plaintext
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡");
+        let message = "WhátisApp+éars to   be the   problem here__°¿¿¿¿¿!!!!++++{{{{{{{}}}}}}}";
+        let encoded = encode(message, &key, &vig_mat, KeyMode::Autokey).unwrap();
+        assert_eq!(message, decode(&encoded, &key, &vig_mat, KeyMode::Autokey).unwrap());
+
+        // the same plaintext/key should produce different
+        // ciphertext under autokey than under the repeating
+        // key, since the running keys diverge immediately
+        // after the key itself is exhausted
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("°¡");
+        let message = "Anup Jadhav";
+        let repeating = encode(message, &key, &vig_mat, KeyMode::Repeating).unwrap();
+        let autokey = encode(message, &key, &vig_mat, KeyMode::Autokey).unwrap();
+        assert_ne!(repeating, autokey);
+    }
+
+    #[test]
+    fn test_encode_and_decode_reject_empty_key() {
+        let vig_mat = VigMatrixWrap::new(&DictWrap::new(&default_alphabet()));
+        let key = SecretKey::new("");
+        assert!(matches!(
+            encode("Hello", &key, &vig_mat, KeyMode::Autokey),
+            Err(ErrorCode::EmptyKey)
+        ));
+        assert!(matches!(
+            decode("Hello", &key, &vig_mat, KeyMode::Autokey),
+            Err(ErrorCode::EmptyKey)
+        ));
+        assert!(matches!(
+            encode("Hello", &key, &vig_mat, KeyMode::Repeating),
+            Err(ErrorCode::EmptyKey)
+        ));
+        assert!(matches!(
+            decode("Hello", &key, &vig_mat, KeyMode::Repeating),
+            Err(ErrorCode::EmptyKey)
+        ));
+    }
+
+    #[test]
+    fn test_cipher_roundtrip_with_custom_alphabet() {
+        let alphabet: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect();
+        let cipher = Cipher::new(&alphabet).unwrap();
+        let key = SecretKey::new("KEY");
+        let message = "HELLOWORLD";
+        let encoded = cipher.encode(message, &key, KeyMode::Repeating).unwrap();
+        assert_eq!(message, cipher.decode(&encoded, &key, KeyMode::Repeating).unwrap());
+    }
+
+    #[test]
+    fn test_cipher_rejects_empty_alphabet() {
+        assert!(matches!(Cipher::new(&[]), Err(ErrorCode::EmptyAlphabet)));
+    }
+
+    #[test]
+    fn test_cipher_rejects_duplicate_alphabet() {
+        let alphabet: Vec<char> = "ABCA".chars().collect();
+        assert!(matches!(
+            Cipher::new(&alphabet),
+            Err(ErrorCode::DuplicateChar('A'))
+        ));
+    }
+
+    #[test]
+    fn test_cipher_default_matches_built_in_alphabet() {
+        let cipher = Cipher::default();
+        assert_eq!(default_alphabet().len(), cipher.dict().len());
     }
 }
@@ -0,0 +1,366 @@
+use std::collections::HashSet;
+
+// A chunked, fountain-code-style transport for streaming
+// a long `cipher::encode` result to the browser in small
+// pieces that can arrive out of order or be partially
+// lost, rather than as one blob the UI has to wait on.
+//
+// The ciphertext is split into `N` fixed-length segments.
+// The first `N` parts sent are just those segments, in
+// order. Every part after that mixes a pseudo-random
+// subset of the segments together by XOR; which segments
+// were mixed is derived solely from the part's sequence
+// number, so the part itself only needs to carry that
+// number plus the mixed payload. The receiver solves for
+// the original segments by watching for a part that
+// reduces to a single unknown segment and substituting it
+// back into every other part it's seen.
+
+#[derive(Debug)]
+pub(crate) enum FountainError {
+    Incomplete,
+    SegmentLenMismatch,
+    InvalidSegmentLen,
+}
+
+// One unit of the stream: either an original segment
+// (`seq < segment_count`) or a combination part (`seq >=
+// segment_count`) whose mixed-in segments are implied by
+// `seq`.
+pub(crate) struct Part {
+    pub(crate) seq: u64,
+    pub(crate) payload: Vec<u8>,
+}
+
+// Splits ciphertext bytes into equal-length, zero-padded
+// segments and emits parts for any sequence number on
+// demand.
+pub(crate) struct Encoder {
+    segments: Vec<Vec<u8>>,
+    segment_len: usize,
+}
+
+impl Encoder {
+    // `segment_len` comes from the streaming/UI layer, so
+    // it isn't guaranteed to be non-zero - `data.chunks`
+    // panics on a zero chunk size, so that has to be
+    // checked here rather than left to panic.
+    pub(crate) fn new(data: &[u8], segment_len: usize) -> Result<Encoder, FountainError> {
+        if segment_len == 0 {
+            return Err(FountainError::InvalidSegmentLen);
+        }
+
+        let mut segments: Vec<Vec<u8>> = data
+            .chunks(segment_len)
+            .map(|chunk| {
+                let mut segment = chunk.to_vec();
+                segment.resize(segment_len, 0);
+                segment
+            })
+            .collect();
+        if segments.is_empty() {
+            segments.push(vec![0u8; segment_len]);
+        }
+        Ok(Encoder {
+            segments,
+            segment_len,
+        })
+    }
+
+    pub(crate) fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    // The first `segment_count()` parts are the original
+    // segments, in order; every part after that is the
+    // XOR of the pseudo-random subset of segments that
+    // `subset_for_seq` picks for this `seq`.
+    pub(crate) fn part(&self, seq: u64) -> Part {
+        let n = self.segments.len();
+        if (seq as usize) < n {
+            return Part {
+                seq,
+                payload: self.segments[seq as usize].clone(),
+            };
+        }
+
+        let mut payload = vec![0u8; self.segment_len];
+        for idx in subset_for_seq(seq, n) {
+            xor_into(&mut payload, &self.segments[idx]);
+        }
+        Part { seq, payload }
+    }
+}
+
+// A part that hasn't been fully resolved yet: `value` is
+// the part's payload with every already-known segment
+// already XORed back out, and `unknown` is the set of
+// segment indices still mixed into it.
+struct PendingPart {
+    unknown: HashSet<usize>,
+    value: Vec<u8>,
+}
+
+// Reassembles segments from parts as they arrive, in any
+// order, resolving a segment the moment some part reduces
+// to it alone and back-substituting into every other part
+// that referenced it.
+pub(crate) struct Decoder {
+    segment_len: usize,
+    solved: Vec<Option<Vec<u8>>>,
+    pending: Vec<PendingPart>,
+}
+
+impl Decoder {
+    // `segment_count` comes from stream metadata the
+    // receiver doesn't control, so - same as
+    // `Encoder::new`'s `segment_len` check - it isn't
+    // guaranteed to be non-zero. A zero count would leave
+    // `subset_for_seq` computing a remainder by zero the
+    // first time `add_part` is called, so that has to be
+    // rejected here rather than left to panic.
+    pub(crate) fn new(segment_count: usize, segment_len: usize) -> Result<Decoder, FountainError> {
+        if segment_count == 0 {
+            return Err(FountainError::InvalidSegmentLen);
+        }
+
+        Ok(Decoder {
+            segment_len,
+            solved: vec![None; segment_count],
+            pending: Vec::new(),
+        })
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.solved.iter().all(Option::is_some)
+    }
+
+    pub(crate) fn add_part(&mut self, part: Part) -> Result<(), FountainError> {
+        if part.payload.len() != self.segment_len {
+            return Err(FountainError::SegmentLenMismatch);
+        }
+
+        let n = self.solved.len();
+        if (part.seq as usize) < n {
+            self.mark_solved(part.seq as usize, part.payload);
+            return Ok(());
+        }
+
+        let mut value = part.payload;
+        let mut unknown = HashSet::new();
+        for idx in subset_for_seq(part.seq, n) {
+            match &self.solved[idx] {
+                Some(segment) => xor_into(&mut value, segment),
+                None => {
+                    unknown.insert(idx);
+                }
+            }
+        }
+
+        if unknown.len() == 1 {
+            let idx = *unknown.iter().next().unwrap();
+            self.mark_solved(idx, value);
+        } else if !unknown.is_empty() {
+            self.pending.push(PendingPart { unknown, value });
+        }
+        // an empty `unknown` means this part only told us
+        // about segments we already knew - redundant, so
+        // there's nothing left to record.
+
+        Ok(())
+    }
+
+    // Records `value` as the solved segment at `idx`,
+    // then sweeps every pending part that mixed it in:
+    // XORing it back out can itself drop a pending part
+    // to a single remaining unknown, which is resolved in
+    // turn.
+    fn mark_solved(&mut self, idx: usize, value: Vec<u8>) {
+        if self.solved[idx].is_some() {
+            return;
+        }
+        self.solved[idx] = Some(value.clone());
+
+        let mut newly_solved = Vec::new();
+        for pending in self.pending.iter_mut() {
+            if pending.unknown.remove(&idx) {
+                xor_into(&mut pending.value, &value);
+                if pending.unknown.len() == 1 {
+                    let remaining = *pending.unknown.iter().next().unwrap();
+                    newly_solved.push((remaining, std::mem::take(&mut pending.value)));
+                }
+            }
+        }
+        self.pending.retain(|pending| pending.unknown.len() > 1);
+
+        for (idx, value) in newly_solved {
+            self.mark_solved(idx, value);
+        }
+    }
+
+    // Concatenates the solved segments and strips the
+    // zero padding `Encoder` added to reach a whole
+    // number of segments, given the original byte length.
+    pub(crate) fn finish(&self, original_len: usize) -> Result<Vec<u8>, FountainError> {
+        if !self.is_complete() {
+            return Err(FountainError::Incomplete);
+        }
+
+        let mut bytes = Vec::with_capacity(self.solved.len() * self.segment_len);
+        for segment in &self.solved {
+            bytes.extend_from_slice(segment.as_ref().unwrap());
+        }
+        bytes.truncate(original_len);
+        Ok(bytes)
+    }
+}
+
+fn xor_into(dst: &mut [u8], src: &[u8]) {
+    for (d, s) in dst.iter_mut().zip(src.iter()) {
+        *d ^= s;
+    }
+}
+
+// Picks the pseudo-random subset of `0..n` mixed into the
+// combination part for `seq`, deterministically, so the
+// receiver can recompute exactly which segments a part
+// references from its sequence number alone. Each
+// segment is included with probability 1/2; on the rare
+// seed that excludes everything, `seq`'s own segment is
+// forced in so every part carries at least one segment.
+fn subset_for_seq(seq: u64, n: usize) -> Vec<usize> {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(seq);
+    let subset: Vec<usize> = (0..n).filter(|_| rng.next_u64() & 1 == 1).collect();
+    if subset.is_empty() {
+        vec![(seq as usize) % n]
+    } else {
+        subset
+    }
+}
+
+// A small, self-contained Xoshiro256** generator, seeded
+// from a single `u64` via SplitMix64 (the scheme the
+// xoshiro authors recommend for expanding a small seed
+// into the generator's full state).
+struct Xoshiro256StarStar {
+    s: [u64; 4],
+}
+
+impl Xoshiro256StarStar {
+    fn seed_from_u64(seed: u64) -> Xoshiro256StarStar {
+        let mut sm_state = seed;
+        let mut next_splitmix = || {
+            sm_state = sm_state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = sm_state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Xoshiro256StarStar {
+            s: [
+                next_splitmix(),
+                next_splitmix(),
+                next_splitmix(),
+                next_splitmix(),
+            ],
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[1]
+            .wrapping_mul(5)
+            .rotate_left(7)
+            .wrapping_mul(9);
+
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fountain_roundtrip_in_order() {
+        let data = b"The quick brown fox jumps over the lazy dog".to_vec();
+        let encoder = Encoder::new(&data, 6).unwrap();
+        let mut decoder = Decoder::new(encoder.segment_count(), 6).unwrap();
+
+        let mut seq = 0;
+        while !decoder.is_complete() {
+            decoder.add_part(encoder.part(seq)).unwrap();
+            seq += 1;
+        }
+
+        assert_eq!(data, decoder.finish(data.len()).unwrap());
+    }
+
+    #[test]
+    fn test_fountain_roundtrip_combination_parts_only() {
+        let data = b"0123456789abcdefghijklmnopqrstuvwxyz".to_vec();
+        let encoder = Encoder::new(&data, 4).unwrap();
+        let n = encoder.segment_count();
+        let mut decoder = Decoder::new(n, 4).unwrap();
+
+        // skip straight past the original segments and
+        // only ever feed combination parts - decoding
+        // must still converge via back-substitution.
+        let mut seq = n as u64;
+        while !decoder.is_complete() {
+            decoder.add_part(encoder.part(seq)).unwrap();
+            seq += 1;
+        }
+
+        assert_eq!(data, decoder.finish(data.len()).unwrap());
+    }
+
+    #[test]
+    fn test_fountain_finish_before_complete_errors() {
+        let data = b"short message".to_vec();
+        let encoder = Encoder::new(&data, 5).unwrap();
+        let decoder = Decoder::new(encoder.segment_count(), 5).unwrap();
+        assert!(matches!(
+            decoder.finish(data.len()),
+            Err(FountainError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_fountain_strips_padding() {
+        // message length isn't a multiple of the segment
+        // length, so the last segment is zero-padded
+        let data = b"abc".to_vec();
+        let encoder = Encoder::new(&data, 8).unwrap();
+        assert_eq!(1, encoder.segment_count());
+
+        let mut decoder = Decoder::new(1, 8).unwrap();
+        decoder.add_part(encoder.part(0)).unwrap();
+        assert_eq!(data, decoder.finish(data.len()).unwrap());
+    }
+
+    #[test]
+    fn test_fountain_rejects_zero_segment_len() {
+        let data = b"short message".to_vec();
+        assert!(matches!(
+            Encoder::new(&data, 0),
+            Err(FountainError::InvalidSegmentLen)
+        ));
+    }
+
+    #[test]
+    fn test_fountain_rejects_zero_segment_count() {
+        assert!(matches!(
+            Decoder::new(0, 8),
+            Err(FountainError::InvalidSegmentLen)
+        ));
+    }
+}
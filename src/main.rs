@@ -1,4 +1,7 @@
+mod armor;
 mod cipher;
+mod fountain;
+mod secret_key;
 use cipher::Hello;
 use sycamore::prelude::*;
 
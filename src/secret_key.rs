@@ -0,0 +1,149 @@
+use std::fmt;
+
+// A byte buffer that overwrites itself with zeroes
+// before it's freed, so a stray copy of sensitive data
+// doesn't linger in a heap snapshot or core dump after
+// it goes out of scope.
+pub(crate) struct Zeroizing(Vec<u8>);
+
+impl Zeroizing {
+    pub(crate) fn new(bytes: Vec<u8>) -> Zeroizing {
+        Zeroizing(bytes)
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("Zeroizing buffer built from valid UTF-8")
+    }
+}
+
+impl Drop for Zeroizing {
+    fn drop(&mut self) {
+        for byte in self.0.iter_mut() {
+            // SAFETY: `byte` comes from a live `&mut u8`,
+            // so the raw pointer it coerces to is valid
+            // and aligned for this write; the volatile
+            // write stops the compiler from optimizing
+            // the zeroing out as a dead store.
+            unsafe { std::ptr::write_volatile(byte, 0) };
+        }
+    }
+}
+
+// Owns the key material that flows into `encode`/
+// `decode`. On top of zeroizing its buffer on drop
+// (see `Zeroizing`), it best-effort locks that buffer
+// into physical memory so the key can't be paged out to
+// swap, where it would outlive the process. Memory
+// locking is unavailable on WASM targets (there's no
+// `mlock`/`VirtualLock` equivalent, and no swap to guard
+// against in the browser sandbox) and is gated behind
+// the `secure-memory` feature everywhere else, since it
+// requires a process-wide locked-pages budget the caller
+// may not want to grant.
+pub(crate) struct SecretKey {
+    buf: Zeroizing,
+    locked: bool,
+}
+
+impl SecretKey {
+    pub(crate) fn new(key: &str) -> SecretKey {
+        let bytes = key.as_bytes().to_vec();
+        let locked = lock_memory(&bytes);
+        SecretKey {
+            buf: Zeroizing::new(bytes),
+            locked,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        self.buf.as_str()
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        if self.locked {
+            unlock_memory(&self.buf.0);
+        }
+        // `self.buf`'s own `Drop` zeroizes the bytes
+        // once this one returns.
+    }
+}
+
+// Deliberately doesn't print the key material.
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretKey").field("buf", &"REDACTED").finish()
+    }
+}
+
+#[cfg(all(unix, not(target_arch = "wasm32"), feature = "secure-memory"))]
+fn lock_memory(bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return false;
+    }
+    // SAFETY: `bytes` is a valid, live allocation for
+    // its own length for the duration of this call.
+    let ret = unsafe { libc::mlock(bytes.as_ptr() as *const libc::c_void, bytes.len()) };
+    ret == 0
+}
+
+#[cfg(all(windows, not(target_arch = "wasm32"), feature = "secure-memory"))]
+fn lock_memory(bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return false;
+    }
+    // SAFETY: `bytes` is a valid, live allocation for
+    // its own length for the duration of this call.
+    let ret = unsafe {
+        winapi::um::memoryapi::VirtualLock(bytes.as_ptr() as *mut winapi::ctypes::c_void, bytes.len())
+    };
+    ret != 0
+}
+
+#[cfg(not(all(not(target_arch = "wasm32"), feature = "secure-memory")))]
+fn lock_memory(_bytes: &[u8]) -> bool {
+    false
+}
+
+#[cfg(all(unix, not(target_arch = "wasm32"), feature = "secure-memory"))]
+fn unlock_memory(bytes: &[u8]) {
+    // SAFETY: `bytes` is the same allocation that was
+    // just locked in `lock_memory`.
+    unsafe {
+        libc::munlock(bytes.as_ptr() as *const libc::c_void, bytes.len());
+    }
+}
+
+#[cfg(all(windows, not(target_arch = "wasm32"), feature = "secure-memory"))]
+fn unlock_memory(bytes: &[u8]) {
+    // SAFETY: `bytes` is the same allocation that was
+    // just locked in `lock_memory`.
+    unsafe {
+        winapi::um::memoryapi::VirtualUnlock(
+            bytes.as_ptr() as *mut winapi::ctypes::c_void,
+            bytes.len(),
+        );
+    }
+}
+
+#[cfg(not(all(not(target_arch = "wasm32"), feature = "secure-memory")))]
+fn unlock_memory(_bytes: &[u8]) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_secret_key_roundtrips_as_str() {
+        let secret = SecretKey::new("°¡! RüST íS CóÓL ¡!°");
+        assert_eq!("°¡! RüST íS CóÓL ¡!°", secret.as_str());
+    }
+
+    #[test]
+    fn test_secret_key_debug_redacts_key() {
+        let secret = SecretKey::new("super-secret-key");
+        assert_eq!("SecretKey { buf: \"REDACTED\" }", format!("{:?}", secret));
+    }
+}